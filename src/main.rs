@@ -2,27 +2,37 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::{
+    collections::HashMap,
     env,
     path::Path,
     sync::{Arc, Mutex},
     thread,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use eframe::{
     egui::{
-        self, CentralPanel, Color32, IconData, RichText, TextEdit, Vec2, ViewportBuilder, Visuals,
-        Widget, WindowLevel,
+        self, CentralPanel, Color32, IconData, RichText, TextEdit, Vec2, ViewportBuilder,
+        ViewportCommand, Visuals, Widget, WindowLevel,
     },
     Frame, HardwareAcceleration, Theme,
 };
 
-use kira::manager::{backend::cpal::CpalBackend, AudioManager, AudioManagerSettings};
+use chrono::TimeZone;
+use cpal::traits::{DeviceTrait, HostTrait};
+use kira::manager::backend::cpal::{CpalBackend, CpalBackendSettings};
+use kira::manager::{AudioManager, AudioManagerSettings};
 use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle};
-use kira::sound::{FromFileError, PlaybackState};
+use kira::sound::{FromFileError, PlaybackState, Region};
+use kira::tween::Tween;
+use kira::Volume;
 use serde::{Deserialize, Serialize};
+use tray_icon::{
+    menu::{Menu, MenuEvent, MenuItem},
+    Icon, TrayIcon, TrayIconBuilder, TrayIconEvent,
+};
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
 enum Status {
     Running,
     Stop,
@@ -33,6 +43,14 @@ enum Status {
     RestWait,
 }
 
+// 倒计时的显示方式：原始秒数，或 m:ss / h:mm:ss 的时钟格式
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+enum DisplayFormat {
+    #[default]
+    RawSeconds,
+    Clock,
+}
+
 fn main() -> Result<(), eframe::Error> {
     let mut auto_backup = Clock::default();
 
@@ -126,14 +144,85 @@ struct Clock {
 
     audio: Audio,
     setting: Setting,
+
+    // "适应窗口"模式下，上一次收敛得到的字号及其生效条件，避免每帧重新搜索
+    fit_cache: Option<FitCache>,
+
+    // 系统托盘；平台不支持或创建失败时保持 None，此时不会尝试最小化到托盘
+    tray: Option<Tray>,
+    // 关闭窗口时若设置了"最小化到托盘"，标记下一帧需要隐藏窗口
+    hide_requested: bool,
+    // 从托盘菜单点击"退出"：即使开启了"最小化到托盘"也应真正退出，而不是再次隐藏
+    force_quit: bool,
+
+    // 新增提示音阈值输入框的临时文本，不持久化
+    new_cue_secs: String,
+
+    // 时间/休息输入框的编辑态缓冲区；未聚焦时才从 setting 重新格式化，
+    // 避免 mm:ss 这类还未输入完整（如 "25:"）的中间态被每帧还原成上一次提交的值
+    run_secs_input: String,
+    rest_secs_input: String,
+
+    // 当前正在进行（尚未结束）的一段专注/休息，用于在其结束时落盘为一条历史记录
+    session_meta: Arc<Mutex<Option<SessionMeta>>>,
+    // 已确定结束但还未写入磁盘的记录，由计时线程统一落盘，避免阻塞 UI 线程
+    pending_records: Arc<Mutex<Vec<SessionRecord>>>,
+    // 今日完成专注次数/时长的缓存；仅在计时线程落盘新记录时重新计算，UI 线程只读取，
+    // 避免在高频重绘（计时运行时每 10ms 一次）下反复重新读取、解析整个历史文件
+    today_stats: Arc<Mutex<(usize, usize)>>,
+}
+
+#[derive(Debug)]
+struct FitCache {
+    digits: usize,
+    panel_width: f32,
+    size: f32,
+}
+
+const MIN_FIT_FONT_SIZE: f32 = 10.0;
+const MAX_FIT_FONT_SIZE: f32 = 400.0;
+
+/// 按 5/6、6/5 的倍率反复调整字号，直到文本宽度落在可用宽度的 [80%, 100%] 区间
+fn fit_font_size(ui: &egui::Ui, text: &str, available_width: f32, mut size: f32) -> f32 {
+    for _ in 0..40 {
+        let width = ui.fonts(|fonts| {
+            fonts
+                .layout_no_wrap(text.to_owned(), egui::FontId::proportional(size), Color32::WHITE)
+                .size()
+                .x
+        });
+
+        if width > available_width {
+            size *= 5.0 / 6.0;
+        } else if width < available_width * 0.8 {
+            size *= 6.0 / 5.0;
+        } else {
+            break;
+        }
+        size = size.clamp(MIN_FIT_FONT_SIZE, MAX_FIT_FONT_SIZE);
+    }
+    size
 }
 
 impl Clock {
     pub fn init(&mut self) {
         // self.min = Arc::new(Mutex::new(0));
 
+        self.audio = Audio::with_device(self.setting.output_device.as_deref());
+        self.tray = Tray::new();
+        self.run_secs_input = format_countdown(self.setting.run_secs, &self.setting.display_format);
+        self.rest_secs_input =
+            format_countdown(self.setting.rest_secs, &self.setting.display_format);
+
         let min_arc = self.countdown.clone();
         let status_arc = self.status.clone();
+        let session_meta_arc = self.session_meta.clone();
+        let pending_arc = self.pending_records.clone();
+        let stats_arc = self.today_stats.clone();
+
+        if let Ok(mut stats) = stats_arc.try_lock() {
+            *stats = History::today_focus_stats();
+        }
 
         thread::spawn(move || loop {
             if let Ok(mut min) = min_arc.try_lock() {
@@ -149,12 +238,25 @@ impl Clock {
                             *status = Status::Rest;
                         } else if *status == Status::RestRunning {
                             *status = Status::RestWait;
+                            finish_session(&session_meta_arc, &pending_arc, true);
                         } else {
                             *status = Status::Wait;
                         }
                     }
                 }
             }
+
+            if let Ok(mut pending) = pending_arc.try_lock() {
+                if !pending.is_empty() {
+                    for record in pending.drain(..) {
+                        History::append(&record);
+                    }
+                    if let Ok(mut stats) = stats_arc.try_lock() {
+                        *stats = History::today_focus_stats();
+                    }
+                }
+            }
+
             thread::sleep(Duration::from_secs(1));
         });
     }
@@ -166,6 +268,16 @@ impl Clock {
         if let Ok(mut status) = self.status.try_lock() {
             *status = Status::Running;
         }
+
+        // 若上一段专注尚未走到 Rest 就被重新开始，记为"未完成"
+        finish_session(&self.session_meta, &self.pending_records, false);
+        if let Ok(mut meta) = self.session_meta.try_lock() {
+            *meta = Some(SessionMeta {
+                start_unix: unix_now(),
+                duration_secs: self.setting.run_secs,
+                phase: SessionPhase::Focus,
+            });
+        }
     }
 
     pub fn check_status(&mut self) {
@@ -176,6 +288,15 @@ impl Clock {
                     *min = self.setting.rest_secs;
                     *status = Status::RestRunning;
                 }
+
+                finish_session(&self.session_meta, &self.pending_records, true);
+                if let Ok(mut meta) = self.session_meta.try_lock() {
+                    *meta = Some(SessionMeta {
+                        start_unix: unix_now(),
+                        duration_secs: self.setting.rest_secs,
+                        phase: SessionPhase::Rest,
+                    });
+                }
             }
 
             auto_next = *status == Status::RestWait && self.setting.auto_next;
@@ -188,42 +309,110 @@ impl Clock {
     pub fn voice_broadcast(&mut self) {
         if let Ok(status) = self.status.try_lock() {
             if let Ok(sec) = self.countdown.try_lock() {
+                let master_volume = self.setting.master_volume;
                 if *status == Status::Running {
-                    match *sec {
-                        90 => self
-                            .audio
-                            .start_play(&format!("{}/assets/audio/90.mp3", current_dir())),
-                        60 => self
-                            .audio
-                            .start_play(&format!("{}/assets/audio/60.mp3", current_dir())),
-                        30 => self
-                            .audio
-                            .start_play(&format!("{}/assets/audio/30.mp3", current_dir())),
-                        10 => self
-                            .audio
-                            .start_play(&format!("{}/assets/audio/10.mp3", current_dir())),
-                        5 => self
-                            .audio
-                            .start_play(&format!("{}/assets/audio/05.mp3", current_dir())),
-                        0 => self
-                            .audio
-                            .start_play(&format!("{}/assets/audio/rest.mp3", current_dir())),
-                        _ => {}
+                    for cue in &self.setting.sound_pack.cues {
+                        if cue.trigger == CueTrigger::Remaining(*sec) {
+                            if let Some(path) = self.setting.sound_pack.resolve(&cue.trigger) {
+                                self.audio.start_play(&path, master_volume);
+                            }
+                        }
+                    }
+                    if *sec == 0 {
+                        if let Some(path) = self.setting.sound_pack.resolve(&CueTrigger::Rest) {
+                            self.audio.start_play(&path, master_volume);
+                        }
                     }
                 }
                 if *status == Status::RestRunning && *sec == 0 {
-                    self.audio
-                        .start_play(&format!("{}/assets/audio/next.mp3", current_dir()));
+                    if let Some(path) = self.setting.sound_pack.resolve(&CueTrigger::Next) {
+                        self.audio.start_play(&path, master_volume);
+                    }
                 }
             }
         }
     }
+
+    /// 在"适应窗口"模式下解析当前应使用的字号，仅在数字位数或面板宽度变化时重新搜索
+    pub fn resolve_fit_size(&mut self, ui: &egui::Ui, text: &str, available_width: f32) -> f32 {
+        let digits = text.chars().count();
+        if let Some(cache) = &self.fit_cache {
+            if cache.digits == digits && (cache.panel_width - available_width).abs() < 1.0 {
+                return cache.size;
+            }
+        }
+
+        let start = self
+            .fit_cache
+            .as_ref()
+            .map(|cache| cache.size)
+            .unwrap_or(self.setting.font_size);
+        let size = fit_font_size(ui, text, available_width, start);
+        self.fit_cache = Some(FitCache {
+            digits,
+            panel_width: available_width,
+            size,
+        });
+        size
+    }
+
+    // 环境音混音：专注时持续播放，休息/暂停时淡出
+    pub fn ambient_mix(&mut self) {
+        let running = matches!(self.status.try_lock().as_deref(), Ok(Status::Running));
+        self.audio.sync_soundscapes(
+            &self.setting.soundscapes,
+            running,
+            self.setting.master_volume,
+        );
+    }
+
+    // 处理托盘菜单点击、托盘图标双击恢复窗口，并刷新托盘图标
+    pub fn poll_tray(&mut self, ctx: &egui::Context) {
+        let Some(tray) = &self.tray else {
+            return;
+        };
+
+        while let Ok(event) = MenuEvent::receiver().try_recv() {
+            if event.id == *tray.start_item.id() {
+                self.start();
+            } else if event.id == *tray.pause_item.id() {
+                if let Ok(mut status) = self.status.try_lock() {
+                    if *status == Status::Running {
+                        *status = Status::Stop;
+                    } else if *status == Status::Stop {
+                        *status = Status::Running;
+                    }
+                }
+            } else if event.id == *tray.quit_item.id() {
+                self.force_quit = true;
+                ctx.send_viewport_cmd(ViewportCommand::Close);
+            }
+        }
+
+        while let Ok(event) = TrayIconEvent::receiver().try_recv() {
+            if let TrayIconEvent::DoubleClick { .. } = event {
+                ctx.send_viewport_cmd(ViewportCommand::Visible(true));
+                ctx.send_viewport_cmd(ViewportCommand::Focus);
+            }
+        }
+
+        if let Ok(status) = self.status.try_lock() {
+            tray.set_status_icon(*status);
+        }
+    }
 }
 
 impl eframe::App for Clock {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
         self.check_status();
         self.voice_broadcast();
+        self.ambient_mix();
+        self.poll_tray(ctx);
+
+        if self.hide_requested {
+            self.hide_requested = false;
+            ctx.send_viewport_cmd(ViewportCommand::Visible(false));
+        }
 
         CentralPanel::default().show(ctx, |ui| {
             // 控制面板
@@ -237,6 +426,21 @@ impl eframe::App for Clock {
                     self.setting.save();
                 }
 
+                if ui
+                    .checkbox(&mut self.setting.auto_fit_font, "自适应字号")
+                    .changed()
+                {
+                    self.fit_cache = None;
+                    self.setting.save();
+                }
+
+                if ui
+                    .checkbox(&mut self.setting.minimize_to_tray_on_close, "关闭时最小化到托盘")
+                    .changed()
+                {
+                    self.setting.save();
+                }
+
                 if ui.button("开始").clicked() {
                     self.start();
                 }
@@ -257,42 +461,193 @@ impl eframe::App for Clock {
             // 输入框
             ui.horizontal(|ui| {
                 ui.label("时间");
-                let mut run_secs = self.setting.run_secs.to_string();
-                if TextEdit::singleline(&mut run_secs)
+                let response = TextEdit::singleline(&mut self.run_secs_input)
                     .desired_width(80.0)
-                    .ui(ui)
-                    .changed()
-                {
-                    if run_secs.trim().is_empty() {
-                        run_secs = String::from("0");
-                    }
-                    if let Ok(num) = run_secs.parse() {
+                    .ui(ui);
+                if response.changed() {
+                    if let Some(num) = parse_countdown(&self.run_secs_input) {
                         self.setting.run_secs = num;
                         self.setting.save();
                     }
                 }
+                if !response.has_focus() {
+                    self.run_secs_input =
+                        format_countdown(self.setting.run_secs, &self.setting.display_format);
+                }
 
                 ui.label("休息");
-                let mut rest_secs = self.setting.rest_secs.to_string();
-                if TextEdit::singleline(&mut rest_secs)
+                let response = TextEdit::singleline(&mut self.rest_secs_input)
                     .desired_width(80.0)
-                    .ui(ui)
-                    .changed()
-                {
-                    if rest_secs.trim().is_empty() {
-                        rest_secs = String::from("0");
-                    }
-                    if let Ok(num) = rest_secs.parse() {
+                    .ui(ui);
+                if response.changed() {
+                    if let Some(num) = parse_countdown(&self.rest_secs_input) {
                         self.setting.rest_secs = num;
                         self.setting.save();
                     }
                 }
+                if !response.has_focus() {
+                    self.rest_secs_input =
+                        format_countdown(self.setting.rest_secs, &self.setting.display_format);
+                }
+
+                let mut clock_format = matches!(self.setting.display_format, DisplayFormat::Clock);
+                if ui.checkbox(&mut clock_format, "时钟格式").changed() {
+                    self.setting.display_format = if clock_format {
+                        DisplayFormat::Clock
+                    } else {
+                        DisplayFormat::RawSeconds
+                    };
+                    self.setting.save();
+                    self.run_secs_input =
+                        format_countdown(self.setting.run_secs, &self.setting.display_format);
+                    self.rest_secs_input =
+                        format_countdown(self.setting.rest_secs, &self.setting.display_format);
+                }
             });
 
+            // 提示音
+            ui.collapsing("提示音", |ui| {
+                if ui.button("打开提示音文件夹").clicked() {
+                    SoundPack::open_sounds_dir();
+                }
+
+                let mut changed = false;
+                let mut remove_idx = None;
+                for (idx, cue) in self.setting.sound_pack.cues.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(cue.trigger.label());
+                        let mut file = cue.file.clone().unwrap_or_default();
+                        if TextEdit::singleline(&mut file)
+                            .desired_width(140.0)
+                            .hint_text("留空使用内置音效")
+                            .ui(ui)
+                            .changed()
+                        {
+                            cue.file = if file.trim().is_empty() {
+                                None
+                            } else {
+                                Some(file)
+                            };
+                            changed = true;
+                        }
+                        if matches!(cue.trigger, CueTrigger::Remaining(_))
+                            && ui.small_button("删除").clicked()
+                        {
+                            remove_idx = Some(idx);
+                        }
+                    });
+                }
+                if let Some(idx) = remove_idx {
+                    self.setting.sound_pack.cues.remove(idx);
+                    changed = true;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("新增剩余秒数阈值");
+                    TextEdit::singleline(&mut self.new_cue_secs)
+                        .desired_width(60.0)
+                        .ui(ui);
+                    if ui.button("添加").clicked() {
+                        if let Ok(secs) = self.new_cue_secs.trim().parse::<usize>() {
+                            let trigger = CueTrigger::Remaining(secs);
+                            if !self
+                                .setting
+                                .sound_pack
+                                .cues
+                                .iter()
+                                .any(|cue| cue.trigger == trigger)
+                            {
+                                self.setting
+                                    .sound_pack
+                                    .cues
+                                    .push(SoundCue { trigger, file: None });
+                                changed = true;
+                            }
+                        }
+                        self.new_cue_secs.clear();
+                    }
+                });
+
+                if changed {
+                    self.setting.save();
+                }
+            });
+
+            // 统计
+            ui.collapsing("统计", |ui| {
+                let (count, total_secs) = self.today_stats.try_lock().map_or((0, 0), |s| *s);
+                ui.label(format!("今日完成专注 {} 次", count));
+                ui.label(format!(
+                    "累计专注时长 {}",
+                    format_countdown(total_secs, &DisplayFormat::Clock)
+                ));
+            });
+
+            // 主音量与输出设备
+            ui.collapsing("音量与输出设备", |ui| {
+                if ui
+                    .add(egui::Slider::new(&mut self.setting.master_volume, 0.0..=1.0).text("主音量"))
+                    .changed()
+                {
+                    self.setting.save();
+                }
+
+                let current_label = self
+                    .setting
+                    .output_device
+                    .clone()
+                    .unwrap_or_else(|| "系统默认".to_string());
+                egui::ComboBox::from_label("输出设备")
+                    .selected_text(current_label)
+                    .show_ui(ui, |ui| {
+                        if ui
+                            .selectable_label(self.setting.output_device.is_none(), "系统默认")
+                            .clicked()
+                        {
+                            self.setting.output_device = None;
+                            self.audio = Audio::with_device(None);
+                            self.setting.save();
+                        }
+                        for device in Audio::list_output_devices() {
+                            let selected = self.setting.output_device.as_deref() == Some(device.as_str());
+                            if ui.selectable_label(selected, &device).clicked() {
+                                self.audio = Audio::with_device(Some(&device));
+                                self.setting.output_device = Some(device);
+                                self.setting.save();
+                            }
+                        }
+                    });
+            });
+
+            // 环境音层
+            if !self.setting.soundscapes.is_empty() {
+                ui.collapsing("环境音", |ui| {
+                    let mut changed = false;
+                    for layer in self.setting.soundscapes.iter_mut() {
+                        ui.horizontal(|ui| {
+                            changed |= ui.checkbox(&mut layer.enabled, &layer.file).changed();
+                            changed |= ui
+                                .add(egui::Slider::new(&mut layer.volume, 0.0..=1.0).text("音量"))
+                                .changed();
+                        });
+                    }
+                    if changed {
+                        self.setting.save();
+                    }
+                });
+            }
+
             // 大屏展示
+            let available_width = ui.available_width();
             ui.centered_and_justified(|ui| {
                 if let Ok(min) = self.countdown.try_lock() {
-                    let mut rich_text = RichText::new(min.to_string()).size(self.setting.font_size);
+                    let text = format_countdown(*min, &self.setting.display_format);
+                    let font_size = if self.setting.auto_fit_font {
+                        self.resolve_fit_size(ui, &text, available_width)
+                    } else {
+                        self.setting.font_size
+                    };
+                    let mut rich_text = RichText::new(text).size(font_size);
                     if let Ok(status) = self.status.try_lock() {
                         if *status == Status::RestRunning {
                             rich_text = rich_text.color(Color32::DARK_GREEN);
@@ -312,6 +667,224 @@ impl eframe::App for Clock {
             }
         }
     }
+
+    fn on_close_event(&mut self) -> bool {
+        if self.force_quit {
+            return true;
+        }
+        if self.setting.minimize_to_tray_on_close && self.tray.is_some() {
+            self.hide_requested = true;
+            false
+        } else {
+            true
+        }
+    }
+}
+
+// 一段专注/休息的阶段类型
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+enum SessionPhase {
+    Focus,
+    Rest,
+}
+
+// 正在进行、尚未结束的一段专注/休息
+#[derive(Debug, Clone, Copy)]
+struct SessionMeta {
+    start_unix: u64,
+    duration_secs: usize,
+    phase: SessionPhase,
+}
+
+// 写入历史日志的一条已结束记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SessionRecord {
+    start_unix: u64,
+    duration_secs: usize,
+    phase: SessionPhase,
+    finished: bool,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// 本地时区今天 00:00:00 对应的 Unix 时间戳，用于按"自然日"而非 UTC 日划分统计
+fn local_day_start_unix() -> u64 {
+    let now = chrono::Local::now();
+    let midnight = now.date_naive().and_hms_opt(0, 0, 0).unwrap();
+    chrono::Local
+        .from_local_datetime(&midnight)
+        .single()
+        .map(|dt| dt.timestamp().max(0) as u64)
+        .unwrap_or_else(unix_now)
+}
+
+/// 若存在尚未结束的一段专注/休息，取出并作为一条记录排队等待落盘
+fn finish_session(
+    session_meta: &Arc<Mutex<Option<SessionMeta>>>,
+    pending_records: &Arc<Mutex<Vec<SessionRecord>>>,
+    finished: bool,
+) {
+    if let Ok(mut meta) = session_meta.try_lock() {
+        if let Some(session) = meta.take() {
+            if let Ok(mut pending) = pending_records.try_lock() {
+                pending.push(SessionRecord {
+                    start_unix: session.start_unix,
+                    duration_secs: session.duration_secs,
+                    phase: session.phase,
+                    finished,
+                });
+            }
+        }
+    }
+}
+
+// 历史记录：以 NDJSON 追加写入，每行一条已结束的专注/休息记录
+struct History;
+
+impl History {
+    pub fn log_path() -> String {
+        format!("{}/data/history.ndjson", current_dir())
+    }
+
+    pub fn append(record: &SessionRecord) {
+        if let Ok(line) = serde_json::to_string(record) {
+            let path = Self::log_path();
+            let mut data = Setting::read_data(&path).unwrap_or_default();
+            if !data.is_empty() {
+                data.push('\n');
+            }
+            data.push_str(&line);
+            let _ = Setting::write_data(&path, data);
+        }
+    }
+
+    /// 返回今日（本地时区）已完成的专注次数与累计专注秒数
+    pub fn today_focus_stats() -> (usize, usize) {
+        let Ok(data) = Setting::read_data(&Self::log_path()) else {
+            return (0, 0);
+        };
+        let today_start = local_day_start_unix();
+        data.lines()
+            .filter_map(|line| serde_json::from_str::<SessionRecord>(line).ok())
+            .filter(|record| {
+                record.phase == SessionPhase::Focus
+                    && record.finished
+                    && record.start_unix >= today_start
+            })
+            .fold((0, 0), |(count, secs), record| {
+                (count + 1, secs + record.duration_secs)
+            })
+    }
+}
+
+// 语音提示的触发点：专注阶段剩余秒数、转入休息、休息结束
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+enum CueTrigger {
+    Remaining(usize),
+    Rest,
+    Next,
+}
+
+impl CueTrigger {
+    pub fn label(&self) -> String {
+        match self {
+            CueTrigger::Remaining(secs) => format!("剩余 {}s", secs),
+            CueTrigger::Rest => "进入休息".to_string(),
+            CueTrigger::Next => "休息结束".to_string(),
+        }
+    }
+
+    /// 内置音效文件名，仅覆盖原先固定的那几个触发点
+    pub fn bundled_asset(&self) -> Option<&'static str> {
+        match self {
+            CueTrigger::Remaining(90) => Some("90.mp3"),
+            CueTrigger::Remaining(60) => Some("60.mp3"),
+            CueTrigger::Remaining(30) => Some("30.mp3"),
+            CueTrigger::Remaining(10) => Some("10.mp3"),
+            CueTrigger::Remaining(5) => Some("05.mp3"),
+            CueTrigger::Rest => Some("rest.mp3"),
+            CueTrigger::Next => Some("next.mp3"),
+            _ => None,
+        }
+    }
+}
+
+// 一个提示音触发点对应的自定义音频文件（相对用户提示音目录），未设置时回退到内置音效
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SoundCue {
+    trigger: CueTrigger,
+    file: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SoundPack {
+    cues: Vec<SoundCue>,
+}
+
+impl SoundPack {
+    /// 用户自定义提示音所在目录，不存在时由 open_sounds_dir 创建；resolve 只拼接路径，不创建目录
+    pub fn sounds_dir() -> String {
+        format!("{}/data/sounds", current_dir())
+    }
+
+    pub fn open_sounds_dir() {
+        let dir = Self::sounds_dir();
+        let _ = std::fs::create_dir_all(&dir);
+        #[cfg(target_os = "windows")]
+        let _ = std::process::Command::new("explorer").arg(&dir).spawn();
+        #[cfg(target_os = "macos")]
+        let _ = std::process::Command::new("open").arg(&dir).spawn();
+        #[cfg(target_os = "linux")]
+        let _ = std::process::Command::new("xdg-open").arg(&dir).spawn();
+    }
+
+    /// 解析触发点对应的音频文件路径：优先用户自定义文件，其次内置音效，否则不播放
+    pub fn resolve(&self, trigger: &CueTrigger) -> Option<String> {
+        let cue = self.cues.iter().find(|cue| &cue.trigger == trigger)?;
+        if let Some(file) = &cue.file {
+            Some(format!("{}/{}", Self::sounds_dir(), file))
+        } else {
+            trigger
+                .bundled_asset()
+                .map(|asset| format!("{}/assets/audio/{}", current_dir(), asset))
+        }
+    }
+}
+
+impl Default for SoundPack {
+    fn default() -> Self {
+        let cues = [90, 60, 30, 10, 5]
+            .into_iter()
+            .map(|secs| SoundCue {
+                trigger: CueTrigger::Remaining(secs),
+                file: None,
+            })
+            .chain([
+                SoundCue {
+                    trigger: CueTrigger::Rest,
+                    file: None,
+                },
+                SoundCue {
+                    trigger: CueTrigger::Next,
+                    file: None,
+                },
+            ])
+            .collect();
+        SoundPack { cues }
+    }
+}
+
+// 单个环境音层（白噪音、雨声等循环音轨）的开关与音量
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SoundscapeLayer {
+    file: String,
+    enabled: bool,
+    volume: f32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -321,6 +894,20 @@ struct Setting {
     auto_next: bool,
     font_size: f32,
     transparent: f32,
+    #[serde(default)]
+    soundscapes: Vec<SoundscapeLayer>,
+    #[serde(default)]
+    auto_fit_font: bool,
+    #[serde(default)]
+    display_format: DisplayFormat,
+    #[serde(default)]
+    minimize_to_tray_on_close: bool,
+    #[serde(default)]
+    sound_pack: SoundPack,
+    #[serde(default = "Setting::default_master_volume")]
+    master_volume: f32,
+    #[serde(default)]
+    output_device: Option<String>,
 }
 impl Setting {
     pub fn new() -> Self {
@@ -330,6 +917,52 @@ impl Setting {
             auto_next: false,
             font_size: 50.0,
             transparent: 1.0,
+            auto_fit_font: false,
+            display_format: DisplayFormat::RawSeconds,
+            minimize_to_tray_on_close: false,
+            sound_pack: SoundPack::default(),
+            master_volume: Self::default_master_volume(),
+            output_device: None,
+            soundscapes: Self::discover_soundscapes()
+                .into_iter()
+                .map(|file| SoundscapeLayer {
+                    file,
+                    enabled: false,
+                    volume: 0.5,
+                })
+                .collect(),
+        }
+    }
+
+    fn default_master_volume() -> f32 {
+        1.0
+    }
+
+    /// 扫描 assets/soundscapes 目录下可用的循环音轨文件名
+    pub fn discover_soundscapes() -> Vec<String> {
+        let dir = format!("{}/assets/soundscapes", current_dir());
+        match std::fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// 将磁盘上实际存在的音轨与已保存的设置合并：保留已有的开关/音量，新增文件默认关闭，已删除的文件被剔除
+    pub fn sync_soundscapes(&mut self) {
+        let found = Self::discover_soundscapes();
+        self.soundscapes.retain(|layer| found.contains(&layer.file));
+        for file in found {
+            if !self.soundscapes.iter().any(|layer| layer.file == file) {
+                self.soundscapes.push(SoundscapeLayer {
+                    file,
+                    enabled: false,
+                    volume: 0.5,
+                });
+            }
         }
     }
 
@@ -381,7 +1014,8 @@ impl Default for Setting {
         }
 
         if let Ok(data) = Self::read_data(&path) {
-            if let Ok(value) = serde_json::from_str::<Setting>(&data) {
+            if let Ok(mut value) = serde_json::from_str::<Setting>(&data) {
+                value.sync_soundscapes();
                 value
             } else {
                 Self::new()
@@ -392,24 +1026,127 @@ impl Default for Setting {
     }
 }
 
+// 系统托盘：最小化时仍可从托盘菜单开始/暂停，图标随状态变化
+struct Tray {
+    tray_icon: TrayIcon,
+    start_item: MenuItem,
+    pause_item: MenuItem,
+    quit_item: MenuItem,
+    icon_wait: Icon,
+    icon_running: Icon,
+    icon_rest: Icon,
+}
+
+impl Tray {
+    pub fn new() -> Option<Self> {
+        let menu = Menu::new();
+        let start_item = MenuItem::new("开始", true, None);
+        let pause_item = MenuItem::new("暂停/继续", true, None);
+        let quit_item = MenuItem::new("退出", true, None);
+        menu.append_items(&[&start_item, &pause_item, &quit_item]).ok()?;
+
+        let icon_wait = load_tray_icon(include_bytes!("../assets/tray/wait.png"))?;
+        let icon_running = load_tray_icon(include_bytes!("../assets/tray/running.png"))?;
+        let icon_rest = load_tray_icon(include_bytes!("../assets/tray/rest.png"))?;
+
+        let tray_icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_icon(icon_wait.clone())
+            .with_tooltip("Clock")
+            .build()
+            .ok()?;
+
+        Some(Tray {
+            tray_icon,
+            start_item,
+            pause_item,
+            quit_item,
+            icon_wait,
+            icon_running,
+            icon_rest,
+        })
+    }
+
+    /// 根据当前计时状态切换托盘图标：专注中 / 休息中 / 其它（等待、暂停）
+    pub fn set_status_icon(&self, status: Status) {
+        let icon = match status {
+            Status::Running => &self.icon_running,
+            Status::RestRunning => &self.icon_rest,
+            _ => &self.icon_wait,
+        };
+        let _ = self.tray_icon.set_icon(Some(icon.clone()));
+    }
+}
+
+fn load_tray_icon(bytes: &[u8]) -> Option<Icon> {
+    let img = image::load_from_memory_with_format(bytes, image::ImageFormat::Png).ok()?;
+    let rgba = img.into_rgba8();
+    let (width, height) = (rgba.width(), rgba.height());
+    Icon::from_rgba(rgba.into_raw(), width, height).ok()
+}
+
 // 语音播报
 struct Audio {
     manager: AudioManager,
     sound_handle: Option<StreamingSoundHandle<FromFileError>>,
+    // 正在循环播放的环境音层，key 为文件名
+    loop_handles: HashMap<String, StreamingSoundHandle<FromFileError>>,
 }
 
 impl Default for Audio {
     fn default() -> Self {
-        let manager = AudioManager::<CpalBackend>::new(AudioManagerSettings::default()).unwrap();
+        Self::with_device(None)
+    }
+}
+
+const SOUNDSCAPE_FADE: Tween = Tween {
+    duration: Duration::from_millis(500),
+    ..Tween::DEFAULT
+};
+
+impl Audio {
+    /// 按设备名重建输出；传 None 使用系统默认设备。找不到同名设备，或该设备本身
+    /// 创建失败（被占用、已拔出等），都回退到系统默认输出，而不是让启动直接崩溃
+    pub fn with_device(device_name: Option<&str>) -> Self {
+        let device = device_name.and_then(|name| {
+            cpal::default_host()
+                .output_devices()
+                .ok()?
+                .find(|device| device.name().map(|n| n == name).unwrap_or(false))
+        });
+
+        let manager = device
+            .and_then(|device| {
+                let settings = AudioManagerSettings {
+                    backend_settings: CpalBackendSettings {
+                        device: Some(device),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+                AudioManager::<CpalBackend>::new(settings).ok()
+            })
+            .unwrap_or_else(|| {
+                AudioManager::<CpalBackend>::new(AudioManagerSettings::default())
+                    .expect("default audio output device should be available")
+            });
+
         Audio {
             manager,
             sound_handle: None,
+            loop_handles: HashMap::new(),
         }
     }
-}
 
-impl Audio {
-    pub fn start_play(&mut self, path: &str) {
+    /// 枚举当前系统可用的音频输出设备名称
+    pub fn list_output_devices() -> Vec<String> {
+        cpal::default_host()
+            .output_devices()
+            .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn start_play(&mut self, path: &str, master_volume: f32) {
         if let Some(sound_handle) = &self.sound_handle {
             if sound_handle.state() == PlaybackState::Playing {
                 return;
@@ -417,9 +1154,48 @@ impl Audio {
         }
 
         if let Ok(sound_data) = StreamingSoundData::from_file(path) {
-            // self.sound_data = Some(sound_data);
-            let play = self.manager.play(sound_data).unwrap();
-            self.sound_handle = Some(play);
+            if let Ok(mut play) = self.manager.play(sound_data) {
+                play.set_volume(Volume::Amplitude(master_volume as f64), Tween::default());
+                self.sound_handle = Some(play);
+            }
+        }
+    }
+
+    /// 根据开关/音量状态，保持每一层环境音层的循环播放与 self.loop_handles 同步
+    pub fn sync_soundscapes(&mut self, layers: &[SoundscapeLayer], running: bool, master_volume: f32) {
+        let wanted: Vec<&SoundscapeLayer> = layers
+            .iter()
+            .filter(|layer| running && layer.enabled)
+            .collect();
+
+        // 淡出并移除不再需要的层
+        let to_stop: Vec<String> = self
+            .loop_handles
+            .keys()
+            .filter(|file| !wanted.iter().any(|layer| &layer.file == *file))
+            .cloned()
+            .collect();
+        for file in to_stop {
+            if let Some(mut handle) = self.loop_handles.remove(&file) {
+                handle.stop(SOUNDSCAPE_FADE);
+            }
+        }
+
+        // 启动新层，刷新已有层的音量
+        for layer in wanted {
+            let volume = Volume::Amplitude((layer.volume * master_volume) as f64);
+            if let Some(handle) = self.loop_handles.get_mut(&layer.file) {
+                handle.set_volume(volume, Tween::default());
+                continue;
+            }
+            let path = format!("{}/assets/soundscapes/{}", current_dir(), layer.file);
+            if let Ok(sound_data) = StreamingSoundData::from_file(path) {
+                let sound_data = sound_data.loop_region(Region::from(..));
+                if let Ok(mut handle) = self.manager.play(sound_data) {
+                    handle.set_volume(volume, Tween::default());
+                    self.loop_handles.insert(layer.file.clone(), handle);
+                }
+            }
         }
     }
 }
@@ -459,6 +1235,42 @@ pub fn application_icon() -> Option<Arc<IconData>> {
     }))
 }
 
+// 将剩余秒数格式化为显示文本：原始秒数，或 m:ss / h:mm:ss
+pub fn format_countdown(secs: usize, format: &DisplayFormat) -> String {
+    if *format == DisplayFormat::RawSeconds {
+        return secs.to_string();
+    }
+
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    let seconds = secs % 60;
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+// 解析用户输入，同时接受原始秒数（"1500"）与时钟格式（"25:00"、"1:00:00"）
+pub fn parse_countdown(input: &str) -> Option<usize> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Some(0);
+    }
+
+    if let Ok(num) = input.parse::<usize>() {
+        return Some(num);
+    }
+
+    let parts: Vec<&str> = input.split(':').collect();
+    let values: Option<Vec<usize>> = parts.iter().map(|part| part.parse::<usize>().ok()).collect();
+    match (parts.len(), values) {
+        (2, Some(values)) => Some(values[0] * 60 + values[1]),
+        (3, Some(values)) => Some(values[0] * 3600 + values[1] * 60 + values[2]),
+        _ => None,
+    }
+}
+
 // 获取当前程序运行路径
 pub fn current_dir() -> String {
     match env::current_dir() {